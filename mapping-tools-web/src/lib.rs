@@ -56,7 +56,15 @@ impl Component for Model {
                 let src = Beatmap::parse(Cursor::new(&self.src)).unwrap();
                 let dst = Beatmap::parse(Cursor::new(&self.dst)).unwrap();
                 let mut dsts = vec![dst];
-                copy_hitsounds(&src, &mut dsts, ExtraOpts { leniency: 2 }).unwrap();
+                copy_hitsounds(
+                    &src,
+                    &mut dsts,
+                    ExtraOpts {
+                        leniency: 2,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
                 self.output = dsts[0].to_string();
                 true
             }
@@ -0,0 +1,220 @@
+use std::io::{BufRead, BufReader, Read};
+
+use anyhow::{anyhow, Result};
+
+/// What a single column does on a given step row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    None,
+    Tap,
+    HoldHead,
+    HoldTail,
+    Shock,
+}
+
+/// A single row of steps, aligned across all columns at once.
+#[derive(Debug, Clone)]
+pub struct StepRow {
+    pub time: f64,
+    pub columns: Vec<StepKind>,
+}
+
+/// A BPM change, effective from `time` (in seconds) onward.
+#[derive(Debug, Clone, Copy)]
+pub struct TempoSegment {
+    pub time: f64,
+    pub bpm: f64,
+}
+
+/// A pause in the chart's timeline, during which no steps are expected.
+#[derive(Debug, Clone, Copy)]
+pub struct StopSegment {
+    pub time: f64,
+    pub duration: f64,
+}
+
+/// One difficulty slot in the chart (e.g. Beginner..Challenge), normalized to `0.0..=1.0`
+/// within the set so it can be spread across a [`crate::convert::ConfigRange`].
+#[derive(Debug, Clone)]
+pub struct DifficultySlot {
+    pub name: String,
+    pub value: f64,
+    pub rows: Vec<StepRow>,
+}
+
+/// A parsed step-chart, mirroring the handful of things an SSQ stream actually carries: a
+/// column count, tempo/stop segments, and one note stream per difficulty slot.
+#[derive(Debug, Clone, Default)]
+pub struct StepChart {
+    pub columns: usize,
+    pub tempo: Vec<TempoSegment>,
+    pub stops: Vec<StopSegment>,
+    pub difficulties: Vec<DifficultySlot>,
+}
+
+impl StepChart {
+    /// Parses the SSQ-style interchange format used by this tool: a `COLUMNS` header,
+    /// `BPM`/`STOP` segments, then one `DIFFICULTY <name> <value>` block per slot containing
+    /// step rows as `<time_seconds> <row>`, where `<row>` has one character per column (`0`
+    /// empty, `1` tap, `2` hold head, `3` hold tail, `M` shock).
+    pub fn parse<R: Read>(reader: R) -> Result<StepChart> {
+        let reader = BufReader::new(reader);
+
+        let mut chart = StepChart::default();
+
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let tag = parts.next().unwrap_or("");
+            match tag {
+                "COLUMNS" => {
+                    chart.columns = parts
+                        .next()
+                        .ok_or_else(|| anyhow!("COLUMNS missing a value"))?
+                        .parse()?;
+                }
+                "BPM" => {
+                    let time = parts.next().ok_or_else(|| anyhow!("BPM missing a time"))?.parse()?;
+                    let bpm = parts.next().ok_or_else(|| anyhow!("BPM missing a value"))?.parse()?;
+                    chart.tempo.push(TempoSegment { time, bpm });
+                }
+                "STOP" => {
+                    let time = parts.next().ok_or_else(|| anyhow!("STOP missing a time"))?.parse()?;
+                    let duration = parts
+                        .next()
+                        .ok_or_else(|| anyhow!("STOP missing a duration"))?
+                        .parse()?;
+                    chart.stops.push(StopSegment { time, duration });
+                }
+                "DIFFICULTY" => {
+                    let name = parts
+                        .next()
+                        .ok_or_else(|| anyhow!("DIFFICULTY missing a name"))?
+                        .to_string();
+                    let value = parts
+                        .next()
+                        .ok_or_else(|| anyhow!("DIFFICULTY missing a normalized value"))?
+                        .parse()?;
+                    chart.difficulties.push(DifficultySlot {
+                        name,
+                        value,
+                        rows: Vec::new(),
+                    });
+                }
+                _ => {
+                    // a bare `<time> <row>` line belongs to the most recently opened difficulty
+                    let time: f64 = tag
+                        .parse()
+                        .map_err(|_| anyhow!("unrecognized step-chart directive `{}`", tag))?;
+                    let row = parts
+                        .next()
+                        .ok_or_else(|| anyhow!("step row at {} has no columns", time))?;
+                    let slot = chart
+                        .difficulties
+                        .last_mut()
+                        .ok_or_else(|| anyhow!("step row at {} precedes any DIFFICULTY block", time))?;
+                    slot.rows.push(StepRow {
+                        time,
+                        columns: parse_row(row, chart.columns)?,
+                    });
+                }
+            }
+        }
+
+        Ok(chart)
+    }
+}
+
+fn parse_row(row: &str, columns: usize) -> Result<Vec<StepKind>> {
+    if row.len() != columns {
+        return Err(anyhow!(
+            "step row `{}` has {} columns, expected {}",
+            row,
+            row.len(),
+            columns
+        ));
+    }
+
+    row.chars()
+        .map(|c| {
+            Ok(match c {
+                '0' => StepKind::None,
+                '1' => StepKind::Tap,
+                '2' => StepKind::HoldHead,
+                '3' => StepKind::HoldTail,
+                'M' => StepKind::Shock,
+                other => return Err(anyhow!("unknown step character `{}`", other)),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_row() {
+        assert_eq!(
+            parse_row("0123M", 5).unwrap(),
+            vec![
+                StepKind::None,
+                StepKind::Tap,
+                StepKind::HoldHead,
+                StepKind::HoldTail,
+                StepKind::Shock,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_row_wrong_width_errors() {
+        assert!(parse_row("01", 4).is_err());
+    }
+
+    #[test]
+    fn test_parse_row_unknown_character_errors() {
+        assert!(parse_row("X", 1).is_err());
+    }
+
+    #[test]
+    fn test_step_chart_parse() {
+        let input = "\
+COLUMNS 4
+BPM 0.0 180.0
+STOP 2.0 0.5
+DIFFICULTY Beginner 0.0
+0.0 1000
+0.5 0100
+1.0 2000
+1.5 3000
+";
+        let chart = StepChart::parse(input.as_bytes()).unwrap();
+
+        assert_eq!(chart.columns, 4);
+        assert_eq!(chart.tempo.len(), 1);
+        assert_eq!(chart.tempo[0].bpm, 180.0);
+        assert_eq!(chart.stops.len(), 1);
+        assert_eq!(chart.stops[0].duration, 0.5);
+
+        assert_eq!(chart.difficulties.len(), 1);
+        let slot = &chart.difficulties[0];
+        assert_eq!(slot.name, "Beginner");
+        assert_eq!(slot.value, 0.0);
+        assert_eq!(slot.rows.len(), 4);
+        assert_eq!(slot.rows[0].columns[0], StepKind::Tap);
+        assert_eq!(slot.rows[2].columns[0], StepKind::HoldHead);
+        assert_eq!(slot.rows[3].columns[0], StepKind::HoldTail);
+    }
+
+    #[test]
+    fn test_step_chart_parse_rejects_row_before_difficulty() {
+        let input = "COLUMNS 1\n0.0 1\n";
+        assert!(StepChart::parse(input.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use libosu::prelude::*;
+
+use crate::convert::chart::{DifficultySlot, StepChart, StepKind, TempoSegment};
+use crate::convert::{ConvertOpts, ShockAction};
+
+/// Maps column `c` of `columns` onto the x coordinate osu!mania uses to pick a column.
+fn column_to_x(c: usize, columns: usize) -> i32 {
+    ((512 * c + 256) / columns) as i32
+}
+
+/// The `y` every mania hit object is written at; osu!mania ignores it and keys off `x` alone.
+const MANIA_Y: i32 = 192;
+
+/// Bit set on a hit object's `type` byte to mark it as a mania hold (libosu has no `HitObjectKind`
+/// for this, so holds are written out as raw `.osu` lines instead of through libosu's object
+/// model).
+const HOLD_TYPE_BIT: u8 = 1 << 7;
+
+/// A mania beatmap plus the raw hold-note lines that still need to be merged into its
+/// `[HitObjects]` section (libosu's `HitObjectKind` has no mania long-note variant).
+pub struct ManiaBeatmap {
+    pub beatmap: Beatmap,
+    pub raw_holds: Vec<String>,
+}
+
+impl ManiaBeatmap {
+    /// Serializes this beatmap with the raw hold lines merged into `[HitObjects]` in
+    /// chronological order (ties broken by original position), matching the sorted-hit-objects
+    /// invariant the rest of this tool relies on (see `get_hit_times`'s doc comment, and
+    /// `apply_hitsounds`, which re-sorts before operating for the same reason). Without this,
+    /// every map `convert` writes would desync other subcommands that re-`Beatmap::parse` it.
+    pub fn render(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        self.beatmap.write(&mut buf)?;
+        let text = String::from_utf8(buf)?;
+
+        if self.raw_holds.is_empty() {
+            return Ok(text);
+        }
+
+        let marker = "[HitObjects]";
+        let split_at = text
+            .find(marker)
+            .ok_or_else(|| anyhow!("written beatmap has no [HitObjects] section"))?
+            + marker.len();
+        let (header, body) = text.split_at(split_at);
+
+        let mut lines: Vec<String> = body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect();
+        lines.extend(self.raw_holds.iter().cloned());
+        lines.sort_by_key(|line| hit_object_line_time(line));
+
+        let mut out = String::from(header);
+        out.push('\n');
+        for line in lines.iter() {
+            out.push_str(line);
+            out.push('\n');
+        }
+
+        Ok(out)
+    }
+}
+
+/// Parses the timestamp (the 3rd comma-separated field) out of a `.osu` hit object line.
+fn hit_object_line_time(line: &str) -> i64 {
+    line.splitn(4, ',').nth(2).and_then(|s| s.parse().ok()).unwrap_or(0)
+}
+
+/// Converts a parsed step-chart into one mania beatmap per difficulty slot.
+pub fn convert_to_mania(chart: &StepChart, opts: &ConvertOpts) -> Result<Vec<ManiaBeatmap>> {
+    let mut beatmaps = Vec::with_capacity(chart.difficulties.len());
+
+    for slot in chart.difficulties.iter() {
+        beatmaps.push(build_difficulty(chart, slot, opts)?);
+    }
+
+    Ok(beatmaps)
+}
+
+fn build_difficulty(chart: &StepChart, slot: &DifficultySlot, opts: &ConvertOpts) -> Result<ManiaBeatmap> {
+    let mut beatmap = Beatmap::default();
+    beatmap.mode = Mode::Mania;
+    beatmap.version = slot.name.clone();
+    beatmap.circle_size = chart.columns as f32;
+    beatmap.hp_drain_rate = opts.hp.map_from(slot.value) as f32;
+    beatmap.overall_difficulty = opts.acc.map_from(slot.value) as f32;
+
+    for tempo in chart.tempo.iter() {
+        beatmap.timing_points.push(uninherited_tp(tempo.time, tempo.bpm));
+    }
+
+    for stop in chart.stops.iter() {
+        // a stop is a pair of uninherited points: one parking the beat length for the pause,
+        // and one immediately after reverting to whatever tempo was in effect before it
+        let original_bpm = bpm_at(&chart.tempo, stop.time);
+        beatmap.timing_points.push(TimingPoint {
+            time: Millis::from_seconds(stop.time),
+            beat_len: stop.duration * 1000.0,
+            volume: 100,
+            sample_set: SampleSet::None,
+            sample_index: 0,
+            kiai: false,
+            uninherited: true,
+            ..Default::default()
+        });
+        beatmap.timing_points.push(uninherited_tp(stop.time + stop.duration, original_bpm));
+    }
+
+    let mut raw_holds = Vec::new();
+    let mut open_holds: HashMap<usize, (f64, i32)> = HashMap::new();
+
+    for row in slot.rows.iter() {
+        for (c, kind) in row.columns.iter().enumerate() {
+            let x = column_to_x(c, chart.columns);
+
+            match kind {
+                StepKind::None => {}
+                StepKind::Tap => beatmap.hit_objects.push(tap_at(row.time, x)),
+                StepKind::HoldHead => {
+                    open_holds.insert(c, (row.time, x));
+                }
+                StepKind::HoldTail => match open_holds.remove(&c) {
+                    Some((start, x)) => raw_holds.push(hold_line(start, row.time, x)),
+                    // a tail with no matching head isn't a long note; fall back to a tap
+                    None => beatmap.hit_objects.push(tap_at(row.time, x)),
+                },
+                StepKind::Shock => match opts.shock_action {
+                    ShockAction::Step => beatmap.hit_objects.push(tap_at(row.time, x)),
+                    ShockAction::Ignore => {}
+                },
+            }
+        }
+    }
+
+    Ok(ManiaBeatmap { beatmap, raw_holds })
+}
+
+/// Finds the bpm in effect at `time`, i.e. the latest tempo segment starting at or before it.
+fn bpm_at(tempo: &[TempoSegment], time: f64) -> f64 {
+    tempo
+        .iter()
+        .filter(|t| t.time <= time)
+        .last()
+        .map(|t| t.bpm)
+        .unwrap_or_else(|| tempo.first().map(|t| t.bpm).unwrap_or(120.0))
+}
+
+fn uninherited_tp(time: f64, bpm: f64) -> TimingPoint {
+    TimingPoint {
+        time: Millis::from_seconds(time),
+        beat_len: 60_000.0 / bpm,
+        volume: 100,
+        sample_set: SampleSet::None,
+        sample_index: 0,
+        kiai: false,
+        uninherited: true,
+        ..Default::default()
+    }
+}
+
+fn tap_at(time: f64, x: i32) -> HitObject {
+    HitObject {
+        pos: Point::new(x, MANIA_Y),
+        start_time: Millis::from_seconds(time),
+        kind: HitObjectKind::Circle,
+        new_combo: false,
+        skip_color: 0,
+        additions: Additions::empty(),
+        sample_info: SampleInfo::default(),
+    }
+}
+
+/// Writes a mania long note as a raw `.osu` hit object line: `x,y,time,type,hitsound,
+/// endTime:hitSample`, with the hold bit set on `type`.
+fn hold_line(start: f64, end: f64, x: i32) -> String {
+    let start_ms = Millis::from_seconds(start).0;
+    let end_ms = Millis::from_seconds(end).0;
+    format!(
+        "{},{},{},{},0,{}:0:0:0:0:",
+        x, MANIA_Y, start_ms, HOLD_TYPE_BIT, end_ms
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_to_x_spreads_evenly() {
+        assert_eq!(column_to_x(0, 4), 64);
+        assert_eq!(column_to_x(1, 4), 192);
+        assert_eq!(column_to_x(2, 4), 320);
+        assert_eq!(column_to_x(3, 4), 448);
+    }
+
+    #[test]
+    fn test_bpm_at_picks_latest_segment_at_or_before_time() {
+        let tempo = vec![
+            TempoSegment { time: 0.0, bpm: 120.0 },
+            TempoSegment { time: 10.0, bpm: 180.0 },
+        ];
+        assert_eq!(bpm_at(&tempo, 5.0), 120.0);
+        assert_eq!(bpm_at(&tempo, 10.0), 180.0);
+        assert_eq!(bpm_at(&tempo, 20.0), 180.0);
+    }
+
+    #[test]
+    fn test_hold_line_sets_hold_bit_and_end_time() {
+        let line = hold_line(1.0, 2.5, 64);
+        assert_eq!(line, "64,192,1000,128,0,2500:0:0:0:0:");
+    }
+
+    #[test]
+    fn test_hit_object_line_time_parses_third_field() {
+        assert_eq!(hit_object_line_time("64,192,1000,128,0,2500:0:0:0:0:"), 1000);
+        assert_eq!(hit_object_line_time(""), 0);
+    }
+
+    #[test]
+    fn test_render_merges_holds_in_chronological_order() {
+        let mut beatmap = Beatmap::default();
+        beatmap.mode = Mode::Mania;
+        // intentionally out of order, to prove render() re-sorts rather than trusting it
+        beatmap.hit_objects.push(tap_at(5.0, 64));
+        beatmap.hit_objects.push(tap_at(1.0, 192));
+
+        let mania = ManiaBeatmap {
+            beatmap,
+            raw_holds: vec![hold_line(2.0, 3.0, 320)],
+        };
+
+        let rendered = mania.render().unwrap();
+        let times: Vec<i64> = rendered
+            .split("[HitObjects]")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(hit_object_line_time)
+            .collect();
+
+        assert_eq!(times, vec![1000, 2000, 5000]);
+    }
+}
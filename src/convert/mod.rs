@@ -0,0 +1,132 @@
+mod chart;
+mod mania;
+
+pub use crate::convert::chart::*;
+pub use crate::convert::mania::*;
+
+use std::fs::File;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+/// A linear mapping from the chart's normalized `0.0..=1.0` difficulty slot onto `start..end`.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl ConfigRange {
+    pub fn map_from(&self, v: f64) -> f64 {
+        v * (self.end - self.start) + self.start
+    }
+}
+
+impl FromStr for ConfigRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.splitn(2, ':');
+        let start = parts
+            .next()
+            .ok_or_else(|| anyhow!("missing range start in `{}`", s))?
+            .parse()?;
+        let end = parts
+            .next()
+            .ok_or_else(|| anyhow!("missing range end in `{}` (expected `start:end`)", s))?
+            .parse()?;
+        Ok(ConfigRange { start, end })
+    }
+}
+
+/// What to do with shock-arrow rows when importing.
+#[derive(Debug, Clone, Copy)]
+pub enum ShockAction {
+    /// Treat shock arrows as ordinary taps.
+    Step,
+    /// Drop shock arrows entirely.
+    Ignore,
+}
+
+impl FromStr for ShockAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "step" => Ok(ShockAction::Step),
+            "ignore" => Ok(ShockAction::Ignore),
+            _ => Err(anyhow!("unknown shock action `{}`, expected `step` or `ignore`", s)),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ConvertOpts {
+    /// The step-chart file to import (SSQ-style: timed step rows plus tempo/stop segments).
+    pub input: PathBuf,
+
+    /// Directory to write the generated .osu files into.
+    #[structopt(short = "o", long = "output", default_value = ".")]
+    pub output_dir: PathBuf,
+
+    /// HP drain range to spread across the chart's difficulty slots, as `low:high`.
+    #[structopt(long = "hp", default_value = "2:4")]
+    pub hp: ConfigRange,
+
+    /// Overall difficulty (accuracy) range to spread across the chart's difficulty slots, as
+    /// `low:high`.
+    #[structopt(long = "acc", default_value = "2:7")]
+    pub acc: ConfigRange,
+
+    /// What to do with shock-arrow rows.
+    #[structopt(long = "shock-action", default_value = "step")]
+    pub shock_action: ShockAction,
+}
+
+pub fn convert_cmd(opts: ConvertOpts) -> Result<()> {
+    let chart = chart::StepChart::parse(File::open(&opts.input)?)?;
+    let beatmaps = mania::convert_to_mania(&chart, &opts)?;
+
+    std::fs::create_dir_all(&opts.output_dir)?;
+    for (slot, mania_beatmap) in chart.difficulties.iter().zip(beatmaps) {
+        let path = opts.output_dir.join(format!("{}.osu", slot.name));
+
+        std::fs::write(&path, mania_beatmap.render()?)?;
+        info!("wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_range_from_str() {
+        let range: ConfigRange = "2:7".parse().unwrap();
+        assert_eq!(range.start, 2.0);
+        assert_eq!(range.end, 7.0);
+    }
+
+    #[test]
+    fn test_config_range_from_str_rejects_missing_end() {
+        assert!("2".parse::<ConfigRange>().is_err());
+    }
+
+    #[test]
+    fn test_config_range_map_from() {
+        let range = ConfigRange { start: 2.0, end: 7.0 };
+        assert_eq!(range.map_from(0.0), 2.0);
+        assert_eq!(range.map_from(1.0), 7.0);
+        assert_eq!(range.map_from(0.5), 4.5);
+    }
+
+    #[test]
+    fn test_shock_action_from_str() {
+        assert!(matches!("step".parse::<ShockAction>().unwrap(), ShockAction::Step));
+        assert!(matches!("ignore".parse::<ShockAction>().unwrap(), ShockAction::Ignore));
+        assert!("explode".parse::<ShockAction>().is_err());
+    }
+}
@@ -0,0 +1,77 @@
+mod osu;
+
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use libosu::prelude::*;
+
+#[derive(Debug, StructOpt)]
+pub struct DifficultyOpts {
+    /// The .osu file(s) to compute a star rating for.
+    pub files: Vec<PathBuf>,
+
+    /// Also print the peak strain of every ~400ms section.
+    #[structopt(short = "v", long = "verbose")]
+    pub verbose: bool,
+}
+
+/// Which per-mode strain model to run; this is the switch new strain variants plug into. Only
+/// `Standard` has a strain model (spacing over time); `compute` returns an error for the other
+/// modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    Standard,
+    Taiko,
+    Mania,
+    Catch,
+}
+
+impl GameMode {
+    fn from_beatmap(beatmap: &Beatmap) -> GameMode {
+        match beatmap.mode {
+            Mode::Osu => GameMode::Standard,
+            Mode::Taiko => GameMode::Taiko,
+            Mode::Mania => GameMode::Mania,
+            Mode::Catch => GameMode::Catch,
+        }
+    }
+
+    fn compute(&self, beatmap: &Beatmap) -> Result<DifficultyReport> {
+        match self {
+            GameMode::Standard => osu::compute(beatmap),
+            other => Err(anyhow!(
+                "difficulty estimation for {:?} is not implemented yet",
+                other
+            )),
+        }
+    }
+}
+
+/// The result of running a strain model over a beatmap.
+#[derive(Debug)]
+pub struct DifficultyReport {
+    pub stars: f64,
+
+    /// Peak strain of each fixed-width section, in time order.
+    pub peaks: Vec<f64>,
+}
+
+pub fn difficulty_cmd(opts: DifficultyOpts) -> Result<()> {
+    for path in opts.files.iter() {
+        let file = File::open(path)?;
+        let beatmap = Beatmap::parse(file)?;
+
+        let mode = GameMode::from_beatmap(&beatmap);
+        let report = mode.compute(&beatmap)?;
+
+        println!("{}: {:.2}*", path.display(), report.stars);
+        if opts.verbose {
+            for (i, peak) in report.peaks.iter().enumerate() {
+                println!("  section {}: {:.2}", i, peak);
+            }
+        }
+    }
+
+    Ok(())
+}
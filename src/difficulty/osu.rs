@@ -0,0 +1,115 @@
+use anyhow::Result;
+use libosu::prelude::*;
+
+use crate::difficulty::DifficultyReport;
+use crate::hitsounds::get_hit_times;
+
+/// Fraction of strain left after one second between objects; higher keeps more strain (less
+/// forgiving).
+const DECAY_BASE: f64 = 0.3;
+
+/// Floor on the time delta between objects, so near-simultaneous objects don't blow up the
+/// spacing/time ratio.
+const MIN_DELTA: f64 = 0.025;
+
+/// Width of a strain-peak bucket, in seconds.
+const SECTION_LENGTH: f64 = 0.4;
+
+/// Falloff applied to each successive (sorted descending) section peak when combining them.
+const PEAK_WEIGHT_DECAY: f64 = 0.9;
+
+/// Tuning constant mapping the combined strain total onto a star value.
+const STAR_SCALE: f64 = 0.15;
+
+/// Computes an osu!standard strain-based star rating for `beatmap`.
+pub fn compute(beatmap: &Beatmap) -> Result<DifficultyReport> {
+    let hit_times = get_hit_times(beatmap, false)?;
+
+    let mut peaks = Vec::new();
+    let mut strain = 0.0f64;
+    let mut section_peak = 0.0f64;
+    let mut section_end = SECTION_LENGTH;
+    let mut prev: Option<(f64, Point<i32>)> = None;
+
+    for (time, idx, _) in hit_times.iter() {
+        let pos = beatmap.hit_objects[*idx].pos;
+
+        if let Some((prev_time, prev_pos)) = prev {
+            let delta = (time - prev_time).max(MIN_DELTA);
+            let dx = (pos.x - prev_pos.x) as f64;
+            let dy = (pos.y - prev_pos.y) as f64;
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            strain = decay_strain(strain, delta) + distance / delta;
+        }
+
+        while *time > section_end {
+            peaks.push(section_peak);
+            section_peak = 0.0;
+            section_end += SECTION_LENGTH;
+        }
+        section_peak = section_peak.max(strain);
+
+        prev = Some((*time, pos));
+    }
+    peaks.push(section_peak);
+
+    Ok(DifficultyReport {
+        stars: combine_peaks(&peaks) * STAR_SCALE,
+        peaks,
+    })
+}
+
+/// Decays `strain` over a time delta of `delta` seconds, mirroring how strain between two
+/// objects fades the further apart in time they are.
+fn decay_strain(strain: f64, delta: f64) -> f64 {
+    strain * DECAY_BASE.powf(delta)
+}
+
+/// Combines per-section peaks into a single value: sorted descending, then summed with
+/// geometric weighting (`peak_i * PEAK_WEIGHT_DECAY^i`), square-rooted to compress the range.
+fn combine_peaks(peaks: &[f64]) -> f64 {
+    let mut sorted = peaks.to_vec();
+    sorted.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+    let mut total = 0.0;
+    let mut weight = 1.0;
+    for peak in sorted.iter() {
+        total += peak * weight;
+        weight *= PEAK_WEIGHT_DECAY;
+    }
+
+    total.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decay_strain_shrinks_over_time() {
+        let decayed = decay_strain(10.0, 1.0);
+        assert_eq!(decayed, 10.0 * DECAY_BASE);
+        assert!(decay_strain(10.0, 2.0) < decayed);
+    }
+
+    #[test]
+    fn test_decay_strain_no_time_is_a_no_op() {
+        assert_eq!(decay_strain(5.0, 0.0), 5.0);
+    }
+
+    #[test]
+    fn test_combine_peaks_weights_largest_peak_most() {
+        let a = combine_peaks(&[1.0, 5.0, 2.0]);
+        let b = combine_peaks(&[5.0, 2.0, 1.0]);
+        assert_eq!(a, b); // order shouldn't matter, it's sorted internally
+
+        // swapping the largest peak out for a smaller one should only ever decrease the total
+        assert!(combine_peaks(&[5.0, 2.0, 1.0]) > combine_peaks(&[1.0, 2.0, 1.0]));
+    }
+
+    #[test]
+    fn test_combine_peaks_empty_is_zero() {
+        assert_eq!(combine_peaks(&[]), 0.0);
+    }
+}
@@ -23,6 +23,40 @@ pub struct ExtraOpts {
     /// Temporal leniency, the number of milliseconds apart two objects can be apart
     #[structopt(short = "l", long = "leniency", default_value = "2")]
     pub leniency: u32,
+
+    /// Don't copy timing point volumes / sample indices / kiai.
+    #[structopt(long = "no-volume")]
+    pub no_volume: bool,
+
+    /// Don't copy hit object additions (whistle/finger/clap/etc).
+    #[structopt(long = "no-additions")]
+    pub no_additions: bool,
+
+    /// Don't copy hit object / slider edge sample sets.
+    #[structopt(long = "no-samplesets")]
+    pub no_samplesets: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ClearHitsoundOpts {
+    /// The paths of maps to clear hitsounds from.
+    pub files: Vec<PathBuf>,
+}
+
+pub fn clear_hitsounds_cmd(opts: ClearHitsoundOpts) -> Result<()> {
+    for path in opts.files.iter() {
+        let mut beatmap = {
+            let file = File::open(path)?;
+            Beatmap::parse(file)?
+        };
+
+        reset_hitsounds(&mut beatmap);
+
+        let file = File::create(path)?;
+        beatmap.write(file)?;
+    }
+
+    Ok(())
 }
 
 pub fn copy_hitsounds_cmd(opts: CopyHitsoundOpts) -> Result<()> {
@@ -167,7 +201,7 @@ fn collect_hitsounds(beatmap: &Beatmap, _opts: &ExtraOpts) -> Result<HitsoundDat
             sample_info,
         });
     }
-    hits.sort_by_key(|h| NotNan::new(h.time).unwrap());
+    legacy_sort_by_key(&mut hits, |h| NotNan::new(h.time).unwrap());
 
     let mut tps = Vec::new();
     for tp in beatmap.timing_points.iter() {
@@ -178,7 +212,7 @@ fn collect_hitsounds(beatmap: &Beatmap, _opts: &ExtraOpts) -> Result<HitsoundDat
             sample_index: tp.sample_index,
         });
     }
-    tps.sort_by_key(|tp| NotNan::new(tp.time).unwrap());
+    legacy_sort_by_key(&mut tps, |tp| NotNan::new(tp.time).unwrap());
 
     Ok(HitsoundData { hits, tps })
 }
@@ -190,8 +224,8 @@ fn apply_hitsounds(
     opts: &ExtraOpts,
 ) -> Result<()> {
     // doesn't hurt to make sure that these lists are sorted
-    beatmap.hit_objects.sort_by_key(|ho| ho.start_time);
-    beatmap.timing_points.sort_by_key(|tp| tp.time);
+    legacy_sort_by_key(&mut beatmap.hit_objects, |ho| ho.start_time);
+    legacy_sort_by_key(&mut beatmap.timing_points, |tp| tp.time);
 
     let leniency = Millis(opts.leniency as i32).as_seconds();
 
@@ -228,6 +262,10 @@ fn apply_hitsounds(
         trace!("hit: {:?}", hit);
 
         if let Some(repeat_idx) = repeat_idx {
+            if skip_slider_repeat(opts) {
+                continue;
+            }
+
             if let HitObjectKind::Slider(info) = &mut ho.kind {
                 // make sure it has that # of repeats
                 info.edge_samplesets.resize(
@@ -237,9 +275,13 @@ fn apply_hitsounds(
                 info.edge_additions
                     .resize(info.num_repeats as usize + 1, Additions::empty());
 
-                info.edge_samplesets[repeat_idx] =
-                    (hit.sample_info.sample_set, hit.sample_info.addition_set);
-                info.edge_additions[repeat_idx] = hit.additions;
+                if !opts.no_samplesets {
+                    info.edge_samplesets[repeat_idx] =
+                        (hit.sample_info.sample_set, hit.sample_info.addition_set);
+                }
+                if !opts.no_additions {
+                    info.edge_additions[repeat_idx] = hit.additions;
+                }
 
                 trace!(
                     "slider @ {} [repeat={}] (time={}) .edge_sets={:?}, .edge_additions={:?}",
@@ -251,36 +293,49 @@ fn apply_hitsounds(
                 );
             }
         } else {
-            ho.sample_info = hit.sample_info.clone();
-            ho.additions = hit.additions.clone();
+            if !opts.no_samplesets {
+                ho.sample_info = hit.sample_info.clone();
+            }
+            if !opts.no_additions {
+                ho.additions = hit.additions.clone();
+            }
         }
     }
 
     // apply the volumes to the timing points
-    for tp in hitsound_data.tps.iter() {
-        let map_tp = match binary_search_for(
-            tp.time,
-            &beatmap.timing_points,
-            |tp| tp.time.as_seconds(),
-            leniency,
-        ) {
-            Ok(idx) => &mut beatmap.timing_points[idx],
-            Err(idx) => {
-                let tp = beatmap.timing_points[idx].clone();
-                beatmap.timing_points.insert(idx, tp);
-                &mut beatmap.timing_points[idx]
-            }
-        };
+    if !opts.no_volume {
+        for tp in hitsound_data.tps.iter() {
+            let map_tp = match binary_search_for(
+                tp.time,
+                &beatmap.timing_points,
+                |tp| tp.time.as_seconds(),
+                leniency,
+            ) {
+                Ok(idx) => &mut beatmap.timing_points[idx],
+                Err(idx) => {
+                    let tp = beatmap.timing_points[idx].clone();
+                    beatmap.timing_points.insert(idx, tp);
+                    &mut beatmap.timing_points[idx]
+                }
+            };
 
-        map_tp.sample_index = tp.sample_index;
-        map_tp.volume = tp.vol;
+            map_tp.sample_index = tp.sample_index;
+            map_tp.volume = tp.vol;
+            map_tp.kiai = tp.kiai;
+        }
     }
 
     Ok(())
 }
 
+/// Whether a slider repeat's sampleset/addition data has nothing left to copy under the
+/// current flags, so the whole repeat can be skipped rather than resized for no reason.
+fn skip_slider_repeat(opts: &ExtraOpts) -> bool {
+    opts.no_samplesets && opts.no_additions
+}
+
 /// Erases all hitsounds from a map.
-fn reset_hitsounds(beatmap: &mut Beatmap) {
+pub fn reset_hitsounds(beatmap: &mut Beatmap) {
     for ho in beatmap.hit_objects.iter_mut() {
         ho.additions = Additions::empty();
         ho.sample_info = SampleInfo::default();
@@ -353,9 +408,27 @@ where
     }
 }
 
+/// Sorts `items` by `key`, explicitly breaking ties by each item's original position.
+///
+/// `Vec::sort_by_key` is already stable, but nothing stops a future refactor (e.g. swapping in
+/// an unstable sort for speed) from quietly dropping that guarantee. Objects that share a
+/// timestamp (stacked circles, a slider head coinciding with a circle) need to keep their
+/// authored order across repeated copy passes for `binary_search_for` to stay idempotent, so
+/// this is the one "legacy" sort every such call site should go through -- mirroring the stable
+/// sort osu! itself uses for the same reason.
+fn legacy_sort_by_key<T, K, F>(items: &mut Vec<T>, mut key: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    let mut indexed: Vec<(usize, T)> = items.drain(..).enumerate().collect();
+    indexed.sort_by(|(ia, a), (ib, b)| key(a).cmp(&key(b)).then(ia.cmp(ib)));
+    items.extend(indexed.into_iter().map(|(_, item)| item));
+}
+
 #[cfg(test)]
 mod tests {
-    use super::binary_search_for;
+    use super::*;
 
     #[test]
     fn test_binary_search() {
@@ -366,6 +439,162 @@ mod tests {
         assert_eq!(binary_search_for(2.05, &list, id, 0.03), Err(3));
         assert_eq!(binary_search_for(1.95, &list, id, 0.03), Err(2));
     }
+
+    #[test]
+    fn test_legacy_sort_preserves_tie_order() {
+        let mut items = vec![(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd'), (1, 'e')];
+        legacy_sort_by_key(&mut items, |(k, _)| *k);
+        assert_eq!(
+            items,
+            vec![(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c'), (1, 'e')]
+        );
+    }
+
+    #[test]
+    fn test_skip_slider_repeat_requires_both_flags() {
+        let mut opts = ExtraOpts::default();
+        assert!(!skip_slider_repeat(&opts));
+
+        opts.no_additions = true;
+        assert!(!skip_slider_repeat(&opts));
+
+        opts.no_samplesets = true;
+        assert!(skip_slider_repeat(&opts));
+    }
+
+    fn circle(time_ms: i32) -> HitObject {
+        HitObject {
+            pos: Point::new(0, 0),
+            start_time: Millis(time_ms),
+            kind: HitObjectKind::Circle,
+            new_combo: false,
+            skip_color: 0,
+            additions: Additions::empty(),
+            sample_info: SampleInfo::default(),
+        }
+    }
+
+    /// A beatmap with one timing point and a circle at each given millisecond offset.
+    fn beatmap_with_circles(times_ms: &[i32]) -> Beatmap {
+        let mut beatmap = Beatmap::default();
+        beatmap.timing_points.push(TimingPoint {
+            time: Millis(0),
+            beat_len: 500.0,
+            volume: 50,
+            sample_set: SampleSet::Soft,
+            sample_index: 1,
+            kiai: false,
+            uninherited: true,
+            ..Default::default()
+        });
+        for &t in times_ms {
+            beatmap.hit_objects.push(circle(t));
+        }
+        beatmap
+    }
+
+    #[test]
+    fn test_reset_hitsounds_clears_circle_additions_and_samples() {
+        let mut beatmap = beatmap_with_circles(&[0]);
+        beatmap.hit_objects[0].additions = Additions::all();
+        beatmap.hit_objects[0].sample_info.sample_set = SampleSet::Drum;
+
+        reset_hitsounds(&mut beatmap);
+
+        assert_eq!(beatmap.hit_objects[0].additions, Additions::empty());
+        assert_eq!(beatmap.hit_objects[0].sample_info.sample_set, SampleSet::None);
+    }
+
+    #[test]
+    fn test_apply_hitsounds_copies_additions_and_samplesets_by_default() {
+        let mut src = beatmap_with_circles(&[0, 1000]);
+        src.hit_objects[0].additions = Additions::all();
+        src.hit_objects[0].sample_info.sample_set = SampleSet::Drum;
+
+        let mut dst = beatmap_with_circles(&[0, 1000]);
+
+        // the CLI's default leniency; the derived `Default` leaves it at 0, which matches nothing
+        let opts = ExtraOpts {
+            leniency: 2,
+            ..Default::default()
+        };
+        let data = collect_hitsounds(&src, &opts).unwrap();
+        apply_hitsounds(&data, &mut dst, &opts).unwrap();
+
+        assert_eq!(dst.hit_objects[0].additions, Additions::all());
+        assert_eq!(dst.hit_objects[0].sample_info.sample_set, SampleSet::Drum);
+    }
+
+    #[test]
+    fn test_apply_hitsounds_no_additions_keeps_samplesets() {
+        let mut src = beatmap_with_circles(&[0, 1000]);
+        src.hit_objects[0].additions = Additions::all();
+        src.hit_objects[0].sample_info.sample_set = SampleSet::Drum;
+
+        let mut dst = beatmap_with_circles(&[0, 1000]);
+
+        let opts = ExtraOpts {
+            leniency: 2,
+            no_additions: true,
+            ..Default::default()
+        };
+        let data = collect_hitsounds(&src, &opts).unwrap();
+        apply_hitsounds(&data, &mut dst, &opts).unwrap();
+
+        assert_eq!(dst.hit_objects[0].additions, Additions::empty());
+        assert_eq!(dst.hit_objects[0].sample_info.sample_set, SampleSet::Drum);
+    }
+
+    #[test]
+    fn test_apply_hitsounds_no_samplesets_keeps_additions() {
+        let mut src = beatmap_with_circles(&[0, 1000]);
+        src.hit_objects[0].additions = Additions::all();
+        src.hit_objects[0].sample_info.sample_set = SampleSet::Drum;
+
+        let mut dst = beatmap_with_circles(&[0, 1000]);
+
+        let opts = ExtraOpts {
+            leniency: 2,
+            no_samplesets: true,
+            ..Default::default()
+        };
+        let data = collect_hitsounds(&src, &opts).unwrap();
+        apply_hitsounds(&data, &mut dst, &opts).unwrap();
+
+        assert_eq!(dst.hit_objects[0].additions, Additions::all());
+        assert_eq!(dst.hit_objects[0].sample_info.sample_set, SampleSet::None);
+    }
+
+    #[test]
+    fn test_apply_hitsounds_no_volume_leaves_timing_points_untouched() {
+        let src = beatmap_with_circles(&[0, 1000]);
+        let mut dst = beatmap_with_circles(&[0, 1000]);
+        dst.timing_points[0].volume = 20;
+        dst.timing_points[0].kiai = true;
+
+        let opts = ExtraOpts {
+            no_volume: true,
+            ..Default::default()
+        };
+        let data = collect_hitsounds(&src, &opts).unwrap();
+        apply_hitsounds(&data, &mut dst, &opts).unwrap();
+
+        assert_eq!(dst.timing_points[0].volume, 20);
+        assert!(dst.timing_points[0].kiai);
+    }
+
+    #[test]
+    fn test_apply_hitsounds_copies_volume_by_default() {
+        let src = beatmap_with_circles(&[0, 1000]);
+        let mut dst = beatmap_with_circles(&[0, 1000]);
+        dst.timing_points[0].volume = 20;
+
+        let opts = ExtraOpts::default();
+        let data = collect_hitsounds(&src, &opts).unwrap();
+        apply_hitsounds(&data, &mut dst, &opts).unwrap();
+
+        assert_eq!(dst.timing_points[0].volume, 50);
+    }
 }
 
 /// Collect a list of EVERY possible time a hitsound could be played
@@ -374,7 +603,10 @@ mod tests {
 /// (timestamp in seconds, index of hitobject, index of repeat (if slider))
 ///
 /// Notably, this assumes that the hit_objects is sorted, since it refers to hit_objects by index
-fn get_hit_times(beatmap: &Beatmap, slider_body: bool) -> Result<Vec<(f64, usize, Option<usize>)>> {
+pub(crate) fn get_hit_times(
+    beatmap: &Beatmap,
+    slider_body: bool,
+) -> Result<Vec<(f64, usize, Option<usize>)>> {
     let mut hit_times = Vec::new();
 
     for (idx, ho) in beatmap.hit_objects.iter().enumerate() {
@@ -403,7 +635,7 @@ fn get_hit_times(beatmap: &Beatmap, slider_body: bool) -> Result<Vec<(f64, usize
         }
     }
 
-    hit_times.sort_by_key(|(t, _, _)| NotNan::new(*t).unwrap());
+    legacy_sort_by_key(&mut hit_times, |(t, _, _)| NotNan::new(*t).unwrap());
 
     Ok(hit_times)
 }
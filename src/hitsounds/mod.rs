@@ -0,0 +1,4 @@
+mod copier;
+
+pub use crate::hitsounds::copier::*;
+pub(crate) use crate::hitsounds::copier::get_hit_times;
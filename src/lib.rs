@@ -8,9 +8,15 @@ extern crate serde;
 extern crate structopt;
 
 mod c;
+mod convert;
+mod difficulty;
 mod hitsounds;
 mod metadata;
+mod timing;
 
 pub use crate::c::*;
+pub use crate::convert::*;
+pub use crate::difficulty::*;
 pub use crate::hitsounds::*;
 pub use crate::metadata::*;
+pub use crate::timing::*;
@@ -21,12 +21,47 @@ enum Subcommand {
         opts: CopyHitsoundOpts,
     },
 
+    /// Wipes all hitsounds (additions, sample sets, edge data) from the given maps.
+    #[structopt(name = "clear-hitsounds")]
+    ClearHitsounds {
+        #[structopt(flatten)]
+        opts: ClearHitsoundOpts,
+    },
+
     /// Extracts metadata from the map and prints to stdout.
     #[structopt(name = "extract-metadata")]
     ExtractMetadata {
         #[structopt(flatten)]
         opts: ExtractMetadataOpts,
     },
+
+    /// Applies metadata (as produced by `extract-metadata`) onto one or more maps.
+    #[structopt(name = "apply-metadata")]
+    ApplyMetadata {
+        #[structopt(flatten)]
+        opts: ApplyMetadataOpts,
+    },
+
+    /// Converts a StepMania/DDR step-chart into a set of mania beatmaps.
+    #[structopt(name = "convert")]
+    Convert {
+        #[structopt(flatten)]
+        opts: ConvertOpts,
+    },
+
+    /// Computes a strain-based star rating for one or more maps.
+    #[structopt(name = "difficulty")]
+    Difficulty {
+        #[structopt(flatten)]
+        opts: DifficultyOpts,
+    },
+
+    /// Proposes a timing point by detecting onsets/tempo in the map's audio track.
+    #[structopt(name = "detect-timing")]
+    DetectTiming {
+        #[structopt(flatten)]
+        opts: DetectTimingOpts,
+    },
 }
 
 impl Subcommand {
@@ -34,7 +69,12 @@ impl Subcommand {
         use Subcommand::*;
         match self {
             CopyHitsounds { opts } => mapping_tools::copy_hitsounds_cmd(opts),
+            ClearHitsounds { opts } => mapping_tools::clear_hitsounds_cmd(opts),
             ExtractMetadata { opts } => mapping_tools::extract_metadata(opts),
+            ApplyMetadata { opts } => mapping_tools::apply_metadata(opts),
+            Convert { opts } => mapping_tools::convert_cmd(opts),
+            Difficulty { opts } => mapping_tools::difficulty_cmd(opts),
+            DetectTiming { opts } => mapping_tools::detect_timing_cmd(opts),
         }?;
         Ok(())
     }
@@ -1,16 +1,21 @@
 use std::fs::File;
+use std::io::{self, Read};
 use std::path::PathBuf;
 
 use anyhow::Result;
 use libosu::prelude::*;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct Metadata {
     pub title: Option<String>,
     pub title_unicode: Option<String>,
     pub artist: Option<String>,
     pub artist_unicode: Option<String>,
+    pub creator: Option<String>,
+    pub version: Option<String>,
+    pub source: Option<String>,
 
+    #[serde(default)]
     pub tags: Vec<String>,
 }
 
@@ -29,6 +34,9 @@ pub fn extract_metadata(opts: ExtractMetadataOpts) -> Result<()> {
         title_unicode: Some(beatmap.title_unicode.clone()),
         artist: Some(beatmap.artist.clone()),
         artist_unicode: Some(beatmap.artist_unicode.clone()),
+        creator: Some(beatmap.creator.clone()),
+        version: Some(beatmap.version.clone()),
+        source: Some(beatmap.source.clone()),
         tags: beatmap.tags.clone(),
     };
 
@@ -41,4 +49,97 @@ pub fn extract_metadata(opts: ExtractMetadataOpts) -> Result<()> {
 pub struct ApplyMetadataOpts {
     /// The list of .osu files to apply the input metadata to.
     pub files: Vec<PathBuf>,
+
+    /// Read the metadata TOML from this file instead of stdin.
+    #[structopt(short = "f", long = "from")]
+    pub from: Option<PathBuf>,
+}
+
+pub fn apply_metadata(opts: ApplyMetadataOpts) -> Result<()> {
+    let mut input = String::new();
+    match &opts.from {
+        Some(path) => {
+            File::open(path)?.read_to_string(&mut input)?;
+        }
+        None => {
+            io::stdin().read_to_string(&mut input)?;
+        }
+    }
+    let metadata: Metadata = toml::from_str(&input)?;
+
+    for path in opts.files.iter() {
+        let mut beatmap = {
+            let file = File::open(path)?;
+            Beatmap::parse(file)?
+        };
+
+        apply_metadata_to_beatmap(&metadata, &mut beatmap);
+
+        let file = File::create(path)?;
+        beatmap.write(file)?;
+    }
+
+    Ok(())
+}
+
+/// Applies every `Some` (or non-empty, for `tags`) field of `metadata` onto `beatmap`, leaving
+/// the rest untouched so partial edits only overwrite what they set.
+fn apply_metadata_to_beatmap(metadata: &Metadata, beatmap: &mut Beatmap) {
+    if let Some(title) = &metadata.title {
+        beatmap.title = title.clone();
+    }
+    if let Some(title_unicode) = &metadata.title_unicode {
+        beatmap.title_unicode = title_unicode.clone();
+    }
+    if let Some(artist) = &metadata.artist {
+        beatmap.artist = artist.clone();
+    }
+    if let Some(artist_unicode) = &metadata.artist_unicode {
+        beatmap.artist_unicode = artist_unicode.clone();
+    }
+    if let Some(creator) = &metadata.creator {
+        beatmap.creator = creator.clone();
+    }
+    if let Some(version) = &metadata.version {
+        beatmap.version = version.clone();
+    }
+    if let Some(source) = &metadata.source {
+        beatmap.source = source.clone();
+    }
+    if !metadata.tags.is_empty() {
+        beatmap.tags = metadata.tags.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_metadata_only_touches_set_fields() {
+        let mut beatmap = Beatmap::default();
+        beatmap.title = "Original Title".to_string();
+        beatmap.artist = "Original Artist".to_string();
+        beatmap.creator = "Original Creator".to_string();
+        beatmap.tags = vec!["original".to_string()];
+
+        let partial: Metadata = toml::from_str(r#"creator = "New Creator""#).unwrap();
+        apply_metadata_to_beatmap(&partial, &mut beatmap);
+
+        assert_eq!(beatmap.creator, "New Creator");
+        assert_eq!(beatmap.title, "Original Title");
+        assert_eq!(beatmap.artist, "Original Artist");
+        assert_eq!(beatmap.tags, vec!["original".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_metadata_empty_tags_do_not_clear_existing() {
+        let mut beatmap = Beatmap::default();
+        beatmap.tags = vec!["kept".to_string()];
+
+        let partial: Metadata = toml::from_str("").unwrap();
+        apply_metadata_to_beatmap(&partial, &mut beatmap);
+
+        assert_eq!(beatmap.tags, vec!["kept".to_string()]);
+    }
 }
@@ -0,0 +1,265 @@
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use libosu::prelude::*;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use super::DetectTimingOpts;
+
+/// Window size for the spectral-flux onset envelope, in samples.
+const WINDOW_SIZE: usize = 1024;
+
+/// Hop size between successive analysis windows, in samples.
+const HOP_SIZE: usize = 512;
+
+pub fn detect_timing_cmd(opts: DetectTimingOpts) -> Result<()> {
+    let mut beatmap = {
+        let file = File::open(&opts.file)?;
+        Beatmap::parse(file)?
+    };
+
+    let audio_path = opts
+        .file
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(&beatmap.audio_filename);
+
+    let (samples, sample_rate) = decode_mono(&audio_path)?;
+    let envelope = onset_envelope(&samples);
+    let frame_rate = sample_rate as f64 / HOP_SIZE as f64;
+
+    let period_frames = estimate_period(&envelope, frame_rate, opts.min_bpm, opts.max_bpm)?;
+    let bpm = 60.0 * frame_rate / period_frames as f64;
+    let phase_frame = estimate_phase(&envelope, period_frames);
+
+    let offset = phase_frame as f64 / frame_rate;
+    let beat_len = 60_000.0 / bpm;
+
+    let tp = TimingPoint {
+        time: Millis::from_seconds(offset),
+        beat_len,
+        volume: 100,
+        sample_set: SampleSet::None,
+        sample_index: 0,
+        kiai: false,
+        uninherited: true,
+        ..Default::default()
+    };
+
+    info!("detected {:.2} bpm at offset {:.1}ms", bpm, tp.time.0);
+
+    if opts.write {
+        beatmap.timing_points.push(tp);
+        let file = File::create(&opts.file)?;
+        beatmap.write(file)?;
+    } else {
+        // matches the `.osu` TimingPoint line layout: time,beatLength,meter,sampleSet,
+        // sampleIndex,volume,uninherited,effects
+        println!(
+            "{},{},4,{},{},{},1,0",
+            tp.time.0, tp.beat_len, tp.sample_set as i32, tp.sample_index, tp.volume
+        );
+    }
+
+    Ok(())
+}
+
+/// Decodes `path` to a single channel of `f32` PCM, downmixing if necessary.
+fn decode_mono(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow!("{} has no decodable audio track", path.display()))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow!("{} is missing a sample rate", path.display()))?;
+
+    let mut decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(symphonia::core::errors::Error::IoError(_)) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = decoder.decode(&packet)?;
+        let spec = *decoded.spec();
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+
+        let channels = spec.channels.count().max(1);
+        for frame in buf.samples().chunks(channels) {
+            let mixed = frame.iter().sum::<f32>() / channels as f32;
+            samples.push(mixed);
+        }
+    }
+
+    Ok((samples, sample_rate))
+}
+
+/// Computes a spectral-flux onset envelope: one value per hop, equal to the sum of positive
+/// bin-to-bin magnitude increases between consecutive windows.
+fn onset_envelope(samples: &[f32]) -> Vec<f64> {
+    let mut planner = FftPlanner::<f64>::new();
+    let fft = planner.plan_fft_forward(WINDOW_SIZE);
+
+    let window: Vec<f64> = (0..WINDOW_SIZE)
+        .map(|i| {
+            // Hann window
+            0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / (WINDOW_SIZE - 1) as f64).cos()
+        })
+        .collect();
+
+    let mut prev_mags = vec![0.0f64; WINDOW_SIZE / 2];
+    let mut envelope = Vec::new();
+
+    let mut pos = 0;
+    while pos + WINDOW_SIZE <= samples.len() {
+        let mut buf: Vec<Complex<f64>> = samples[pos..pos + WINDOW_SIZE]
+            .iter()
+            .zip(window.iter())
+            .map(|(s, w)| Complex::new(*s as f64 * w, 0.0))
+            .collect();
+        fft.process(&mut buf);
+
+        let mut flux = 0.0;
+        for (bin, c) in buf.iter().take(WINDOW_SIZE / 2).enumerate() {
+            let mag = c.norm();
+            let diff = mag - prev_mags[bin];
+            if diff > 0.0 {
+                flux += diff;
+            }
+            prev_mags[bin] = mag;
+        }
+
+        envelope.push(flux);
+        pos += HOP_SIZE;
+    }
+
+    envelope
+}
+
+/// Estimates the dominant period (in envelope frames) within `[min_bpm, max_bpm]` by
+/// autocorrelating the onset envelope and picking the strongest lag.
+fn estimate_period(envelope: &[f64], frame_rate: f64, min_bpm: f64, max_bpm: f64) -> Result<usize> {
+    let min_lag = (frame_rate * 60.0 / max_bpm).floor().max(1.0) as usize;
+    let max_lag = (frame_rate * 60.0 / min_bpm).ceil() as usize;
+
+    if envelope.len() <= max_lag {
+        return Err(anyhow!("audio track is too short to estimate tempo"));
+    }
+
+    let mean = envelope.iter().sum::<f64>() / envelope.len() as f64;
+    let centered: Vec<f64> = envelope.iter().map(|v| v - mean).collect();
+
+    let mut best_lag = min_lag;
+    let mut best_score = f64::MIN;
+    for lag in min_lag..=max_lag {
+        let score: f64 = centered
+            .iter()
+            .zip(centered.iter().skip(lag))
+            .map(|(a, b)| a * b)
+            .sum();
+        if score > best_score {
+            best_score = score;
+            best_lag = lag;
+        }
+    }
+
+    Ok(best_lag)
+}
+
+/// Chooses the phase (in envelope frames) of the onset grid that best lines up with the
+/// strongest onsets, by sliding the first period across the envelope and picking the offset
+/// whose grid samples sum to the largest total.
+fn estimate_phase(envelope: &[f64], period_frames: usize) -> usize {
+    let mut best_phase = 0;
+    let mut best_score = f64::MIN;
+
+    for phase in 0..period_frames.min(envelope.len()) {
+        let score: f64 = envelope
+            .iter()
+            .skip(phase)
+            .step_by(period_frames)
+            .sum();
+        if score > best_score {
+            best_score = score;
+            best_phase = phase;
+        }
+    }
+
+    best_phase
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a synthetic onset envelope with a sharp pulse every `period` frames, offset by
+    /// `phase` frames, for `periods` repetitions.
+    fn pulse_train(period: usize, phase: usize, periods: usize) -> Vec<f64> {
+        let len = phase + period * periods + 1;
+        let mut envelope = vec![0.0; len];
+        let mut i = phase;
+        while i < len {
+            envelope[i] = 1.0;
+            i += period;
+        }
+        envelope
+    }
+
+    #[test]
+    fn test_estimate_period_recovers_known_lag() {
+        // frame_rate chosen so the 60..240 bpm band covers lags 5..20
+        let frame_rate = 20.0;
+        let envelope = pulse_train(10, 0, 8);
+
+        let period = estimate_period(&envelope, frame_rate, 60.0, 240.0).unwrap();
+        assert_eq!(period, 10);
+    }
+
+    #[test]
+    fn test_estimate_period_rejects_short_envelope() {
+        let envelope = pulse_train(10, 0, 1);
+        assert!(estimate_period(&envelope, 20.0, 60.0, 240.0).is_err());
+    }
+
+    #[test]
+    fn test_estimate_phase_finds_offset_pulses() {
+        let envelope = pulse_train(10, 3, 8);
+        assert_eq!(estimate_phase(&envelope, 10), 3);
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(feature = "audio")]
+mod detect;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+#[derive(Debug, StructOpt)]
+pub struct DetectTimingOpts {
+    /// The .osu file whose audio track should be analyzed.
+    pub file: PathBuf,
+
+    /// Lowest BPM to consider when estimating tempo.
+    #[structopt(long = "min-bpm", default_value = "60")]
+    pub min_bpm: f64,
+
+    /// Highest BPM to consider when estimating tempo.
+    #[structopt(long = "max-bpm", default_value = "240")]
+    pub max_bpm: f64,
+
+    /// Write the detected timing point into the map instead of just printing it.
+    #[structopt(short = "w", long = "write")]
+    pub write: bool,
+}
+
+/// Decodes the map's audio and proposes an uninherited timing point from onset/tempo detection.
+///
+/// This pulls in an audio decoder and an FFT, which the core hitsound/metadata tooling doesn't
+/// need, so the implementation lives behind the `audio` cargo feature.
+#[cfg(feature = "audio")]
+pub fn detect_timing_cmd(opts: DetectTimingOpts) -> Result<()> {
+    detect::detect_timing_cmd(opts)
+}
+
+#[cfg(not(feature = "audio"))]
+pub fn detect_timing_cmd(_opts: DetectTimingOpts) -> Result<()> {
+    Err(anyhow!(
+        "mapping-tools was built without the `audio` feature; rebuild with `--features audio` to use detect-timing"
+    ))
+}